@@ -1,22 +1,240 @@
+use super::slot::{alloc_slot, recycle_slot, take_slot};
 use super::{Task};
 use std::{
+    alloc::Layout,
     any::Any,
     future::Future,
-    sync::atomic::{AtomicUsize},
+    panic::{catch_unwind, AssertUnwindSafe},
+    pin::Pin,
+    ptr::NonNull,
+    sync::{atomic::AtomicUsize, Mutex},
+    task::{Context, Poll, Waker},
 };
 
-type FutureError = Box<dyn Any + Send + 'static>;
+/// The payload passed to `panic!` by a task that panicked while polling.
+pub type FutureError = Box<dyn Any + Send + 'static>;
+
+/// What became of a [`FutureTask`], as reported to a waiting
+/// [`JoinHandle`](super::join::JoinHandle).
+pub enum TaskOutcome<T> {
+    Output(T),
+    Panic(FutureError),
+    Cancelled,
+}
 
 #[repr(C, usize)]
 enum FutureData<F: Future> {
     Pending(F),
     Ready(F::Output),
     Error(FutureError),
+    /// The task was aborted before its future ran to completion.
+    Cancelled,
+    /// The output, panic payload, or cancellation has already been handed
+    /// out by `take_output`; nothing left to take.
+    Taken,
 }
 
 #[repr(C)]
 pub struct FutureTask<F: Future> {
     task: Task,
     ref_count: AtomicUsize,
-    data: FutureData<F>,
+    waiter: Mutex<Option<Waker>>,
+    /// Guarded by a `Mutex` rather than accessed through raw pointers: a
+    /// `JoinHandle` is `Send` and so `take_output`/`cancel` can legitimately
+    /// run on a different thread than whichever is driving `poll`.
+    data: Mutex<FutureData<F>>,
+}
+
+impl<F: Future> FutureTask<F> {
+    /// Allocates a new task wrapping `future`, reusing a same-`Layout`
+    /// allocation from this worker's free list when one is available
+    /// instead of going back to the global allocator.
+    pub(crate) fn spawn(task: Task, future: F) -> NonNull<Self> {
+        let layout = Layout::new::<Self>();
+        let ptr = take_slot(layout).unwrap_or_else(|| alloc_slot(layout)).cast::<Self>();
+        // SAFETY: `ptr` points at `layout`-sized, uninitialized (or freshly
+        // allocated) memory; writing a fresh `Self` into it is sound either way.
+        unsafe {
+            ptr.as_ptr().write(FutureTask {
+                task,
+                ref_count: AtomicUsize::new(1),
+                waiter: Mutex::new(None),
+                data: Mutex::new(FutureData::Pending(future)),
+            });
+        }
+        ptr
+    }
+
+    /// Polls the inner future, catching any unwinding panic so a single
+    /// misbehaving task can't unwind across the executor and poison the
+    /// worker driving it.
+    ///
+    /// # Safety
+    /// `self` must be the pinned, stable address the task was spawned at;
+    /// the caller is responsible for upholding the usual `Pin` guarantees
+    /// for as long as `data` stays in the `Pending` state.
+    pub(crate) unsafe fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_unchecked_mut();
+        let mut data = this.data.lock().unwrap();
+        let fut = match &mut *data {
+            FutureData::Pending(fut) => Pin::new_unchecked(fut),
+            FutureData::Ready(_)
+            | FutureData::Error(_)
+            | FutureData::Cancelled
+            | FutureData::Taken => return Poll::Ready(()),
+        };
+
+        match catch_unwind(AssertUnwindSafe(|| fut.poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(output)) => {
+                *data = FutureData::Ready(output);
+                drop(data);
+                this.wake_waiter();
+                Poll::Ready(())
+            }
+            Err(payload) => {
+                *data = FutureData::Error(payload);
+                drop(data);
+                this.wake_waiter();
+                Poll::Ready(())
+            }
+        }
+    }
+
+    /// Registers `waker` to be woken the next time this task finishes, so a
+    /// [`JoinHandle`](super::join::JoinHandle) polled before completion gets
+    /// re-polled once an output is available rather than hanging forever.
+    pub(crate) fn register_waker(&self, waker: &Waker) {
+        let mut slot = self.waiter.lock().unwrap();
+        if !matches!(&*slot, Some(existing) if existing.will_wake(waker)) {
+            *slot = Some(waker.clone());
+        }
+    }
+
+    /// Wakes whichever [`JoinHandle`](super::join::JoinHandle) last
+    /// registered interest, if any.
+    fn wake_waiter(&self) {
+        if let Some(waker) = self.waiter.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    /// The reference count shared between the executor's own task pointer
+    /// and every outstanding [`JoinHandle`](super::join::JoinHandle).
+    pub(crate) fn ref_count(&self) -> &AtomicUsize {
+        &self.ref_count
+    }
+
+    /// `true` once `data` has left `Pending`, i.e. the task produced an
+    /// output, panicked, or was cancelled.
+    pub(crate) fn is_finished(&self) -> bool {
+        !matches!(&*self.data.lock().unwrap(), FutureData::Pending(_))
+    }
+
+    /// Takes the finished value out of `data`, if any, leaving `Taken`
+    /// behind so a repeated call observes no output rather than double
+    /// taking it.
+    ///
+    /// Safe to call from any thread, including one racing a concurrent
+    /// `poll` or `cancel`: `data` is guarded by a `Mutex`, so the two never
+    /// observe a half-written state.
+    pub(crate) fn take_output(&self) -> Option<TaskOutcome<F::Output>> {
+        let mut data = self.data.lock().unwrap();
+        if matches!(&*data, FutureData::Pending(_)) {
+            return None;
+        }
+        match std::mem::replace(&mut *data, FutureData::Taken) {
+            FutureData::Ready(output) => Some(TaskOutcome::Output(output)),
+            FutureData::Error(payload) => Some(TaskOutcome::Panic(payload)),
+            FutureData::Cancelled => Some(TaskOutcome::Cancelled),
+            FutureData::Taken => None,
+            FutureData::Pending(_) => unreachable!("checked above"),
+        }
+    }
+
+    /// Drops the pending future in place, aborting the task before it runs
+    /// to completion. Safe to call from any thread; see `take_output`.
+    pub(crate) fn cancel(&self) {
+        let mut data = self.data.lock().unwrap();
+        if matches!(&*data, FutureData::Pending(_)) {
+            *data = FutureData::Cancelled;
+            drop(data);
+            self.wake_waiter();
+        }
+    }
+
+    /// Releases a task allocation once its `ref_count` has dropped to zero.
+    ///
+    /// Drops the task in place and hands the raw allocation back to this
+    /// worker's free list, so the next `FutureTask` spawned with a matching
+    /// `Layout` can reuse it instead of allocating anew.
+    ///
+    /// # Safety
+    /// `task` must not be accessed by any other reference after this call.
+    pub(crate) unsafe fn free(task: NonNull<Self>) {
+        std::ptr::drop_in_place(task.as_ptr());
+        recycle_slot(task.cast(), Layout::new::<Self>());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::{Wake, Waker};
+    use std::sync::Arc;
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+        fn wake_by_ref(self: &Arc<Self>) {}
+    }
+
+    struct Panics;
+
+    impl Future for Panics {
+        type Output = ();
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            panic!("boom");
+        }
+    }
+
+    #[test]
+    fn panicking_future_is_caught_and_reported() {
+        let task = FutureTask::spawn(Task::new(), Panics);
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        let finished = unsafe { Pin::new_unchecked(&mut *task.as_ptr()).poll(&mut cx) };
+        assert!(finished.is_ready());
+
+        let outcome = unsafe { task.as_ref().take_output() };
+        assert!(matches!(outcome, Some(TaskOutcome::Panic(_))));
+        // A second take observes nothing; the slot was left `Taken`.
+        assert!(unsafe { task.as_ref().take_output() }.is_none());
+
+        unsafe { FutureTask::free(task) };
+    }
+
+    struct Never;
+
+    impl Future for Never {
+        type Output = ();
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn cancel_before_completion_reports_cancelled() {
+        let task = FutureTask::spawn(Task::new(), Never);
+        unsafe {
+            task.as_ref().cancel();
+            assert!(task.as_ref().is_finished());
+            assert!(matches!(
+                task.as_ref().take_output(),
+                Some(TaskOutcome::Cancelled)
+            ));
+            FutureTask::free(task);
+        }
+    }
 }
\ No newline at end of file
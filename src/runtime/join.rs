@@ -0,0 +1,198 @@
+use super::future::{FutureTask, TaskOutcome};
+use std::{
+    any::Any,
+    fmt,
+    future::Future,
+    pin::Pin,
+    ptr::NonNull,
+    sync::atomic::Ordering,
+    task::{Context, Poll},
+};
+
+/// The reason a [`JoinHandle`] failed to produce its task's output.
+pub enum JoinError {
+    /// The task panicked; the payload is the value passed to `panic!`.
+    Panic(Box<dyn Any + Send + 'static>),
+    /// The task was aborted before it completed.
+    Cancelled,
+}
+
+impl fmt::Debug for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Panic(_) => f.write_str("JoinError::Panic(..)"),
+            JoinError::Cancelled => f.write_str("JoinError::Cancelled"),
+        }
+    }
+}
+
+/// A handle to a spawned [`FutureTask`] that can be awaited for its output.
+///
+/// Holds one reference against the task's `ref_count`; dropping the handle
+/// without awaiting it releases that reference without cancelling the task.
+pub struct JoinHandle<F: Future> {
+    task: NonNull<FutureTask<F>>,
+}
+
+unsafe impl<F: Future + Send> Send for JoinHandle<F> where F::Output: Send {}
+
+impl<F: Future> JoinHandle<F> {
+    /// Wraps a task pointer, taking out a new reference on its `ref_count`.
+    ///
+    /// # Safety
+    /// `task` must point at a live `FutureTask` allocated by this runtime.
+    pub(crate) unsafe fn new(task: NonNull<FutureTask<F>>) -> Self {
+        task.as_ref().ref_count().fetch_add(1, Ordering::Relaxed);
+        JoinHandle { task }
+    }
+
+    /// Returns `true` once the task has finished, panicked, or been aborted.
+    pub fn is_finished(&self) -> bool {
+        unsafe { self.task.as_ref().is_finished() }
+    }
+
+    /// Drops the pending future early, cancelling the task in place.
+    pub fn abort(&self) {
+        unsafe { self.task.as_ref().cancel() }
+    }
+}
+
+impl<F: Future> Future for JoinHandle<F> {
+    type Output = Result<F::Output, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Register before checking, so a task that finishes between the two
+        // still wakes us rather than leaving this handle parked forever.
+        unsafe { self.task.as_ref().register_waker(cx.waker()) };
+        match unsafe { self.task.as_ref().take_output() } {
+            Some(TaskOutcome::Output(output)) => Poll::Ready(Ok(output)),
+            Some(TaskOutcome::Panic(payload)) => Poll::Ready(Err(JoinError::Panic(payload))),
+            Some(TaskOutcome::Cancelled) => Poll::Ready(Err(JoinError::Cancelled)),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<F: Future> Drop for JoinHandle<F> {
+    fn drop(&mut self) {
+        unsafe {
+            if self.task.as_ref().ref_count().fetch_sub(1, Ordering::AcqRel) == 1 {
+                FutureTask::free(self.task);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Task;
+    use std::{
+        pin::Pin,
+        sync::{
+            atomic::{AtomicBool, Ordering as AtomicOrdering},
+            Arc,
+        },
+        task::{Context, Wake, Waker},
+    };
+
+    struct FlagWaker(AtomicBool);
+
+    impl Wake for FlagWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, AtomicOrdering::SeqCst);
+        }
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.store(true, AtomicOrdering::SeqCst);
+        }
+    }
+
+    struct CompletesOnFirstPoll;
+
+    impl Future for CompletesOnFirstPoll {
+        type Output = ();
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            Poll::Ready(())
+        }
+    }
+
+    // Regression test: polling a still-pending handle used to drop the
+    // waker on the floor, so a task completing afterward never woke the
+    // executor back up.
+    #[test]
+    fn wakes_handle_after_task_completes() {
+        let task = FutureTask::spawn(Task::new(), CompletesOnFirstPoll);
+        let mut handle = unsafe { JoinHandle::new(task) };
+
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(matches!(
+            Pin::new(&mut handle).poll(&mut cx),
+            Poll::Pending
+        ));
+        assert!(!flag.0.load(AtomicOrdering::SeqCst));
+
+        let noop = Waker::from(Arc::new(FlagWaker(AtomicBool::new(false))));
+        let mut task_cx = Context::from_waker(&noop);
+        let finished = unsafe { Pin::new_unchecked(&mut *task.as_ptr()).poll(&mut task_cx) };
+        assert!(finished.is_ready());
+
+        assert!(flag.0.load(AtomicOrdering::SeqCst));
+        assert!(matches!(
+            Pin::new(&mut handle).poll(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+    }
+
+    struct SpinsOnce(AtomicBool);
+
+    impl Future for SpinsOnce {
+        type Output = ();
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0.swap(true, AtomicOrdering::SeqCst) {
+                Poll::Ready(())
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    // Regression test: `JoinHandle` is `Send`, so `abort`/polling a handle
+    // can legitimately race a different thread driving the task's `poll`.
+    // `FutureTask::data` used to be touched through raw pointers with no
+    // synchronization between the two; this just needs to run cleanly
+    // under a sanitizer/miri rather than assert a particular outcome.
+    #[test]
+    fn concurrent_abort_and_poll_do_not_race() {
+        let task = FutureTask::spawn(Task::new(), SpinsOnce(AtomicBool::new(false)));
+        let handle = unsafe { JoinHandle::new(task) };
+
+        // `task` is a raw `NonNull` and so not itself `Send`; ferry it
+        // across as an address and reconstruct it on the other side. The
+        // task is kept alive for the whole test by `handle`.
+        let task_addr = task.as_ptr() as usize;
+        let driver = std::thread::spawn(move || {
+            let task = NonNull::new(task_addr as *mut FutureTask<SpinsOnce>).unwrap();
+            let waker = Waker::from(Arc::new(FlagWaker(AtomicBool::new(false))));
+            let mut cx = Context::from_waker(&waker);
+            for _ in 0..1_000 {
+                if unsafe { Pin::new_unchecked(&mut *task.as_ptr()).poll(&mut cx) }.is_ready() {
+                    break;
+                }
+            }
+        });
+
+        for _ in 0..1_000 {
+            handle.abort();
+            if handle.is_finished() {
+                break;
+            }
+        }
+
+        driver.join().unwrap();
+        assert!(handle.is_finished());
+    }
+}
@@ -0,0 +1,13 @@
+mod future;
+pub mod join;
+mod slot;
+pub mod spawn;
+pub mod supervisor;
+pub mod unordered;
+
+pub(crate) use future::FutureTask;
+pub use future::{FutureError, TaskOutcome};
+pub use join::{JoinError, JoinHandle};
+pub use spawn::{Spawn, SpawnError, SpawnStatus};
+pub use supervisor::{ExitStatus, RestartPolicy, Supervisor};
+pub use unordered::Unordered;
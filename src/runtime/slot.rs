@@ -0,0 +1,75 @@
+use std::{
+    alloc::{alloc, Layout},
+    cell::RefCell,
+    collections::HashMap,
+    ptr::NonNull,
+};
+
+thread_local! {
+    /// Raw, type-erased allocations freed by this worker, bucketed by
+    /// `Layout` so a later spawn with an identical size and alignment can
+    /// reuse one instead of going back to the allocator.
+    ///
+    /// This free list is keyed by *whichever thread calls `recycle_slot`*,
+    /// not by whichever thread originally spawned the task. `FutureTask::free`
+    /// can run from `JoinHandle::drop`, and a `JoinHandle` is `Send`, so a
+    /// task spawned on one worker can be freed on another: the allocation
+    /// then lands in the freeing thread's list instead of the spawning
+    /// worker's, where a future `take_slot` for that `Layout` would actually
+    /// reuse it. It isn't unsound, just a missed reuse in that case — worst
+    /// case it sits here until this thread exits, at which point it's
+    /// leaked rather than deallocated, same as any other unclaimed entry.
+    static FREE_SLOTS: RefCell<HashMap<Layout, Vec<NonNull<u8>>>> = RefCell::new(HashMap::new());
+}
+
+/// Hands a raw allocation back to this worker's free list for reuse,
+/// instead of deallocating it immediately.
+///
+/// # Safety
+/// `ptr` must have been allocated (directly or via a prior `take_slot`)
+/// with exactly `layout`, and the caller must have already dropped
+/// whatever was living at `ptr` in place.
+pub(crate) unsafe fn recycle_slot(ptr: NonNull<u8>, layout: Layout) {
+    FREE_SLOTS.with(|slots| slots.borrow_mut().entry(layout).or_default().push(ptr));
+}
+
+/// Pops a recycled allocation matching `layout` off this worker's free
+/// list, if one is available.
+pub(crate) fn take_slot(layout: Layout) -> Option<NonNull<u8>> {
+    FREE_SLOTS.with(|slots| slots.borrow_mut().get_mut(&layout).and_then(Vec::pop))
+}
+
+/// Allocates a fresh slot of `layout`, for when no recycled allocation of
+/// a matching size/align is on hand.
+pub(crate) fn alloc_slot(layout: Layout) -> NonNull<u8> {
+    // SAFETY: `layout` is non-zero sized for every `FutureTask<F>`, which
+    // always carries a `Task` and an `AtomicUsize` ahead of `F`.
+    match NonNull::new(unsafe { alloc(layout) }) {
+        Some(ptr) => ptr,
+        None => std::alloc::handle_alloc_error(layout),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_slot_is_empty_for_an_unused_layout() {
+        let layout = Layout::new::<[u8; 257]>();
+        assert!(take_slot(layout).is_none());
+    }
+
+    #[test]
+    fn recycled_slot_is_reused_before_allocating() {
+        let layout = Layout::new::<[u8; 258]>();
+        let ptr = alloc_slot(layout);
+        unsafe { recycle_slot(ptr, layout) };
+
+        let reused = take_slot(layout).expect("recycled slot should be handed back");
+        assert_eq!(reused, ptr);
+        assert!(take_slot(layout).is_none());
+
+        unsafe { std::alloc::dealloc(reused.as_ptr(), layout) };
+    }
+}
@@ -0,0 +1,142 @@
+use super::join::JoinHandle;
+use std::{error, fmt, future::Future, pin::Pin};
+
+/// Why a [`Spawn`] implementation refused a task.
+#[derive(Debug)]
+pub enum SpawnError {
+    /// The executor is shutting down and refuses new work.
+    ShuttingDown,
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpawnError::ShuttingDown => f.write_str("executor is shutting down"),
+        }
+    }
+}
+
+impl error::Error for SpawnError {}
+
+/// Whether a [`Spawn`] implementation is still accepting new work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnStatus {
+    Running,
+    ShuttingDown,
+}
+
+/// The public entry point for submitting work to an executor.
+///
+/// Decouples task submission from any concrete runtime type, so libraries
+/// can be generic over `impl Spawn` rather than depending on a concrete
+/// runtime type, and `zap`'s handle can be plugged in wherever a generic
+/// spawner is expected.
+pub trait Spawn {
+    /// Submits `future` for execution and returns a handle to its eventual
+    /// output or panic.
+    fn spawn<F>(&self, future: F) -> Result<JoinHandle<F>, SpawnError>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static;
+
+    /// Submits an already type-erased future, for callers that can't name
+    /// the concrete future type being spawned.
+    fn spawn_boxed(
+        &self,
+        future: Pin<Box<dyn Future<Output = ()> + Send>>,
+    ) -> Result<(), SpawnError>;
+
+    /// Whether this executor is still accepting new work.
+    fn status(&self) -> SpawnStatus;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{FutureTask, Task};
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        task::{Context, Wake, Waker},
+    };
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+        fn wake_by_ref(self: &Arc<Self>) {}
+    }
+
+    /// A minimal [`Spawn`] impl that drives every task to completion inline,
+    /// on the caller's thread, before returning its handle. Exists only to
+    /// prove `Spawn` is actually implementable by a runtime handle, not a
+    /// production executor.
+    struct InlineExecutor {
+        shutting_down: AtomicBool,
+    }
+
+    impl Spawn for InlineExecutor {
+        fn spawn<F>(&self, future: F) -> Result<JoinHandle<F>, SpawnError>
+        where
+            F: Future + Send + 'static,
+            F::Output: Send + 'static,
+        {
+            if self.shutting_down.load(Ordering::Acquire) {
+                return Err(SpawnError::ShuttingDown);
+            }
+            let task = FutureTask::spawn(Task::new(), future);
+            let waker = Waker::from(Arc::new(NoopWaker));
+            let mut cx = Context::from_waker(&waker);
+            while unsafe { Pin::new_unchecked(&mut *task.as_ptr()).poll(&mut cx) }.is_pending() {}
+            Ok(unsafe { JoinHandle::new(task) })
+        }
+
+        fn spawn_boxed(
+            &self,
+            mut future: Pin<Box<dyn Future<Output = ()> + Send>>,
+        ) -> Result<(), SpawnError> {
+            if self.shutting_down.load(Ordering::Acquire) {
+                return Err(SpawnError::ShuttingDown);
+            }
+            let waker = Waker::from(Arc::new(NoopWaker));
+            let mut cx = Context::from_waker(&waker);
+            while future.as_mut().poll(&mut cx).is_pending() {}
+            Ok(())
+        }
+
+        fn status(&self) -> SpawnStatus {
+            if self.shutting_down.load(Ordering::Acquire) {
+                SpawnStatus::ShuttingDown
+            } else {
+                SpawnStatus::Running
+            }
+        }
+    }
+
+    #[test]
+    fn generic_over_spawn_accepts_inline_executor() {
+        let exec = InlineExecutor {
+            shutting_down: AtomicBool::new(false),
+        };
+        fn submit<S: Spawn, F>(spawner: &S, future: F) -> Result<JoinHandle<F>, SpawnError>
+        where
+            F: Future + Send + 'static,
+            F::Output: Send + 'static,
+        {
+            spawner.spawn(future)
+        }
+        let handle = submit(&exec, async { 1 + 1 }).expect("executor accepts work");
+        assert!(handle.is_finished());
+    }
+
+    #[test]
+    fn shutting_down_refuses_new_work() {
+        let exec = InlineExecutor {
+            shutting_down: AtomicBool::new(true),
+        };
+        assert_eq!(exec.status(), SpawnStatus::ShuttingDown);
+        assert!(matches!(exec.spawn(async {}), Err(SpawnError::ShuttingDown)));
+    }
+}
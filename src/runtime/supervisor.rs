@@ -0,0 +1,244 @@
+use super::future::FutureError;
+use crate::timer::Sleep;
+use std::{
+    future::Future,
+    panic::{catch_unwind, AssertUnwindSafe},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+const DEFAULT_RESTART_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// When a supervised task should be restarted after it stops running.
+pub enum RestartPolicy {
+    /// Restart unconditionally, whether the task finished cleanly or panicked.
+    Always,
+    /// Restart only after a panic; a clean finish ends supervision.
+    OnPanic,
+    /// Never restart; the first exit, clean or not, ends supervision.
+    Never,
+}
+
+/// How a supervised task's most recent run ended.
+pub enum ExitStatus<T> {
+    Success(T),
+    Panic(FutureError),
+}
+
+enum Stage<F: Future> {
+    Running(Pin<Box<F>>),
+    Backoff(Sleep),
+}
+
+/// Restarts a task built from `factory` whenever it stops running, per
+/// `policy`, waiting `restart_timeout` between attempts so a crash-looping
+/// task backs off instead of spinning the executor. Gives up once
+/// `max_restarts` is reached and reports the last exit status.
+pub struct Supervisor<F, Fac>
+where
+    F: Future,
+    Fac: Fn() -> F,
+{
+    factory: Fac,
+    policy: RestartPolicy,
+    restart_timeout: Duration,
+    max_restarts: Option<usize>,
+    restarts: usize,
+    stage: Stage<F>,
+    last_exit: Option<ExitStatus<F::Output>>,
+}
+
+impl<F, Fac> Supervisor<F, Fac>
+where
+    F: Future,
+    Fac: Fn() -> F,
+{
+    pub fn new(factory: Fac) -> Self {
+        let first = (factory)();
+        Supervisor {
+            factory,
+            policy: RestartPolicy::OnPanic,
+            restart_timeout: DEFAULT_RESTART_TIMEOUT,
+            max_restarts: None,
+            restarts: 0,
+            stage: Stage::Running(Box::pin(first)),
+            last_exit: None,
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RestartPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_restart_timeout(mut self, timeout: Duration) -> Self {
+        self.restart_timeout = timeout;
+        self
+    }
+
+    pub fn with_max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+
+    /// How many times this supervisor has restarted its task so far.
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    /// The outcome of the most recent run that was followed by a restart, if
+    /// any. The final run's outcome is returned from `poll` and is not
+    /// stored here.
+    pub fn last_exit(&self) -> Option<&ExitStatus<F::Output>> {
+        self.last_exit.as_ref()
+    }
+
+    fn should_restart(&self, exit: &ExitStatus<F::Output>) -> bool {
+        let permitted_by_policy = match (&self.policy, exit) {
+            (RestartPolicy::Always, _) => true,
+            (RestartPolicy::OnPanic, ExitStatus::Panic(_)) => true,
+            (RestartPolicy::OnPanic, ExitStatus::Success(_)) => false,
+            (RestartPolicy::Never, _) => false,
+        };
+        permitted_by_policy && self.max_restarts.is_none_or(|max| self.restarts < max)
+    }
+}
+
+impl<F, Fac> Future for Supervisor<F, Fac>
+where
+    F: Future,
+    Fac: Fn() -> F,
+{
+    type Output = ExitStatus<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        loop {
+            match &mut this.stage {
+                Stage::Running(fut) => {
+                    let exit = match catch_unwind(AssertUnwindSafe(|| fut.as_mut().poll(cx))) {
+                        Ok(Poll::Pending) => return Poll::Pending,
+                        Ok(Poll::Ready(output)) => ExitStatus::Success(output),
+                        Err(payload) => ExitStatus::Panic(payload),
+                    };
+
+                    if this.should_restart(&exit) {
+                        this.restarts += 1;
+                        this.last_exit = Some(exit);
+                        this.stage = Stage::Backoff(Sleep::new(this.restart_timeout));
+                    } else {
+                        return Poll::Ready(exit);
+                    }
+                }
+                Stage::Backoff(sleep) => match unsafe { Pin::new_unchecked(sleep) }.poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(()) => {
+                        let fresh = (this.factory)();
+                        this.stage = Stage::Running(Box::pin(fresh));
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicUsize as StdAtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Wake, Waker},
+    };
+
+    struct FlagWaker(AtomicBool);
+
+    impl Wake for FlagWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Busy-polls `fut` to completion, bounded so a regression that never
+    /// resolves fails the test instead of hanging the suite.
+    fn drive<F: Future>(mut fut: Pin<&mut F>) -> F::Output {
+        let flag = Arc::new(FlagWaker(AtomicBool::new(true)));
+        let waker = Waker::from(flag);
+        let mut cx = Context::from_waker(&waker);
+        for _ in 0..10_000 {
+            match fut.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => std::thread::yield_now(),
+            }
+        }
+        panic!("future never resolved");
+    }
+
+    struct PanicsThenSucceeds(Arc<AtomicBool>);
+
+    impl Future for PanicsThenSucceeds {
+        type Output = u32;
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<u32> {
+            if !self.0.swap(true, Ordering::SeqCst) {
+                panic!("first run always fails");
+            }
+            Poll::Ready(7)
+        }
+    }
+
+    #[test]
+    fn restarts_on_panic_and_reports_success() {
+        let has_run_once = Arc::new(AtomicBool::new(false));
+        let factory_state = Arc::clone(&has_run_once);
+        let mut supervisor = Box::pin(
+            Supervisor::new(move || PanicsThenSucceeds(Arc::clone(&factory_state)))
+                .with_policy(RestartPolicy::OnPanic)
+                .with_restart_timeout(Duration::from_millis(1)),
+        );
+        let exit = drive(supervisor.as_mut());
+        assert!(matches!(exit, ExitStatus::Success(7)));
+        assert_eq!(supervisor.restarts(), 1);
+    }
+
+    struct AlwaysPanics;
+
+    impl Future for AlwaysPanics {
+        type Output = ();
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            panic!("always fails");
+        }
+    }
+
+    #[test]
+    fn never_policy_reports_first_exit_without_restarting() {
+        let mut supervisor = Box::pin(Supervisor::new(|| AlwaysPanics).with_policy(RestartPolicy::Never));
+        let exit = drive(supervisor.as_mut());
+        assert!(matches!(exit, ExitStatus::Panic(_)));
+        assert_eq!(supervisor.restarts(), 0);
+    }
+
+    #[test]
+    fn stops_restarting_once_max_restarts_is_reached() {
+        let attempts = Arc::new(StdAtomicUsize::new(0));
+        let counted = Arc::clone(&attempts);
+        let mut supervisor = Box::pin(
+            Supervisor::new(move || {
+                counted.fetch_add(1, Ordering::SeqCst);
+                AlwaysPanics
+            })
+            .with_policy(RestartPolicy::Always)
+            .with_restart_timeout(Duration::from_millis(1))
+            .with_max_restarts(2),
+        );
+        let exit = drive(supervisor.as_mut());
+        assert!(matches!(exit, ExitStatus::Panic(_)));
+        assert_eq!(supervisor.restarts(), 2);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,407 @@
+use super::future::{FutureTask, TaskOutcome};
+use std::{
+    future::Future,
+    pin::Pin,
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+/// One entry in the ready queue: wraps a pushed task together with the
+/// Treiber-stack link used to splice it onto `ReadyQueue::head` and the
+/// refcount shared between `Unordered`, the queue link itself, and every
+/// clone of its waker.
+struct Node<F: Future> {
+    task: NonNull<FutureTask<F>>,
+    queue: Arc<ReadyQueue<F>>,
+    next: AtomicPtr<Node<F>>,
+    queued: AtomicBool,
+    ref_count: AtomicUsize,
+    /// Set once this node's task has produced an outcome. A future is free
+    /// to wake itself (including `wake_by_ref`) from inside the very `poll`
+    /// call that returns `Ready`, which can relink a node onto the ready
+    /// queue after `poll_next` has already decided to finish and remove it.
+    /// This flag lets a later drain recognise that stray relink and release
+    /// it without touching `task`, which may already be freed.
+    finished: AtomicBool,
+}
+
+/// A lock-free LIFO stack of tasks that have signalled readiness since the
+/// last `poll_next`, so a wake only ever enqueues the one task that fired
+/// rather than requiring a sweep over every child.
+struct ReadyQueue<F: Future> {
+    head: AtomicPtr<Node<F>>,
+    parent: Mutex<Option<Waker>>,
+}
+
+impl<F: Future> ReadyQueue<F> {
+    fn new() -> Self {
+        ReadyQueue {
+            head: AtomicPtr::new(std::ptr::null_mut()),
+            parent: Mutex::new(None),
+        }
+    }
+
+    /// Pushes `node` onto the stack unless it's already queued, then wakes
+    /// whoever is currently polling the combinator.
+    ///
+    /// Every successful link takes out a reference on `node`'s `ref_count`,
+    /// owned by the queue itself rather than by any waker, so a node can
+    /// never be freed while it's still reachable from `head`.
+    fn push(&self, node: *mut Node<F>) {
+        unsafe {
+            if (*node).queued.swap(true, Ordering::AcqRel) {
+                return;
+            }
+            (*node).ref_count.fetch_add(1, Ordering::Relaxed);
+        }
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            unsafe { (*node).next.store(head, Ordering::Relaxed) };
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => break,
+                Err(actual) => head = actual,
+            }
+        }
+        if let Some(waker) = self.parent.lock().unwrap().as_ref() {
+            waker.wake_by_ref();
+        }
+    }
+
+    /// Takes every currently-queued node at once.
+    fn drain(&self) -> *mut Node<F> {
+        self.head.swap(std::ptr::null_mut(), Ordering::AcqRel)
+    }
+
+    /// Splices an already-linked chain of still-queued nodes back onto the
+    /// stack. Used when a drained batch is abandoned partway through so the
+    /// untouched remainder isn't lost (every node in `chain` must already
+    /// have `queued == true`, as it does for any node still sitting in a
+    /// chain returned by `drain`).
+    fn requeue_chain(&self, chain: *mut Node<F>) {
+        if chain.is_null() {
+            return;
+        }
+        let mut tail = chain;
+        unsafe {
+            while !(*tail).next.load(Ordering::Relaxed).is_null() {
+                tail = (*tail).next.load(Ordering::Relaxed);
+            }
+        }
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            unsafe { (*tail).next.store(head, Ordering::Relaxed) };
+            match self
+                .head
+                .compare_exchange_weak(head, chain, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => break,
+                Err(actual) => head = actual,
+            }
+        }
+        if let Some(waker) = self.parent.lock().unwrap().as_ref() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+unsafe fn clone_raw<F: Future>(ptr: *const ()) -> RawWaker {
+    let node = ptr as *const Node<F>;
+    (*node).ref_count.fetch_add(1, Ordering::Relaxed);
+    RawWaker::new(ptr, vtable::<F>())
+}
+
+unsafe fn wake_by_ref_raw<F: Future>(ptr: *const ()) {
+    let node = ptr as *const Node<F>;
+    (*node).queue.push(node as *mut Node<F>);
+}
+
+unsafe fn wake_raw<F: Future>(ptr: *const ()) {
+    wake_by_ref_raw::<F>(ptr);
+    drop_raw::<F>(ptr);
+}
+
+unsafe fn drop_raw<F: Future>(ptr: *const ()) {
+    let node = ptr as *const Node<F>;
+    if (*node).ref_count.fetch_sub(1, Ordering::AcqRel) == 1 {
+        drop(Box::from_raw(node as *mut Node<F>));
+    }
+}
+
+/// Releases the one `FutureTask` reference `Unordered::push` took
+/// ownership of, freeing the task once no other reference remains.
+unsafe fn release_task<F: Future>(task: NonNull<FutureTask<F>>) {
+    if task.as_ref().ref_count().fetch_sub(1, Ordering::AcqRel) == 1 {
+        FutureTask::free(task);
+    }
+}
+
+fn vtable<F: Future>() -> &'static RawWakerVTable {
+    // `Table::<F>::VALUE` is monomorphized once per `F`, giving each
+    // instantiation its own `'static` vtable without a hand-written `static`
+    // per future type.
+    struct Table<F>(std::marker::PhantomData<F>);
+    impl<F: Future> Table<F> {
+        const VALUE: RawWakerVTable = RawWakerVTable::new(
+            clone_raw::<F>,
+            wake_raw::<F>,
+            wake_by_ref_raw::<F>,
+            drop_raw::<F>,
+        );
+    }
+    &Table::<F>::VALUE
+}
+
+fn make_waker<F: Future>(node: *mut Node<F>) -> Waker {
+    unsafe { (*node).ref_count.fetch_add(1, Ordering::Relaxed) };
+    let raw = RawWaker::new(node as *const (), vtable::<F>());
+    unsafe { Waker::from_raw(raw) }
+}
+
+/// Drives a dynamic set of [`FutureTask`]s concurrently and yields each
+/// one's output as it finishes, in completion order rather than the order
+/// tasks were pushed. Only tasks that have signalled readiness are polled,
+/// so growing the set doesn't make every poll more expensive.
+pub struct Unordered<F: Future> {
+    queue: Arc<ReadyQueue<F>>,
+    nodes: Vec<NonNull<Node<F>>>,
+}
+
+impl<F: Future> Unordered<F> {
+    pub fn new() -> Self {
+        Unordered {
+            queue: Arc::new(ReadyQueue::new()),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Adds `task` to the set. Takes ownership of the reference `task`
+    /// represents; it is polled, and eventually freed, by this combinator.
+    pub fn push(&mut self, task: NonNull<FutureTask<F>>) {
+        let node = Box::into_raw(Box::new(Node {
+            task,
+            queue: Arc::clone(&self.queue),
+            next: AtomicPtr::new(std::ptr::null_mut()),
+            queued: AtomicBool::new(false),
+            ref_count: AtomicUsize::new(1),
+            finished: AtomicBool::new(false),
+        }));
+        // Every freshly-pushed task is polled at least once, the same way
+        // a future is always polled once right after it's spawned.
+        self.queue.push(node);
+        self.nodes.push(unsafe { NonNull::new_unchecked(node) });
+    }
+
+    /// How many tasks are still tracked by this combinator.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn remove(&mut self, node: *mut Node<F>) {
+        if let Some(pos) = self.nodes.iter().position(|n| n.as_ptr() == node) {
+            self.nodes.swap_remove(pos);
+        }
+        unsafe { drop_raw::<F>(node as *const ()) };
+    }
+
+    /// Polls the set for the next task to finish.
+    ///
+    /// Returns `Poll::Ready(None)` once the set is empty, `Poll::Pending`
+    /// if every task is still running, or `Poll::Ready(Some(outcome))` for
+    /// the next task to reach `FutureData::Ready`/`Error`.
+    pub fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<TaskOutcome<F::Output>>> {
+        if self.nodes.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            *self.queue.parent.lock().unwrap() = Some(cx.waker().clone());
+
+            let mut ready = self.queue.drain();
+            if ready.is_null() {
+                return Poll::Pending;
+            }
+
+            while !ready.is_null() {
+                let node_ptr = ready;
+                let node = unsafe { &*node_ptr };
+                ready = node.next.load(Ordering::Relaxed);
+                node.queued.store(false, Ordering::Release);
+
+                // This node is no longer linked anywhere, so release the
+                // reference `ReadyQueue::push` took out on its behalf. A
+                // stray relink from a future that woke itself just before
+                // finishing (see `Node::finished`) may drop this to zero
+                // and free the node right here; in that case there is
+                // nothing left to poll, so move on without touching `task`.
+                let was_already_finished = node.finished.load(Ordering::Acquire);
+                unsafe { drop_raw::<F>(node_ptr as *const ()) };
+                if was_already_finished {
+                    continue;
+                }
+
+                let waker = make_waker(node_ptr);
+                let mut task_cx = Context::from_waker(&waker);
+                let finished = unsafe {
+                    Pin::new_unchecked(&mut *node.task.as_ptr()).poll(&mut task_cx)
+                };
+
+                if finished.is_ready() {
+                    let outcome = unsafe { node.task.as_ref().take_output() }
+                        .expect("FutureData left Pending after Poll::Ready");
+                    unsafe { release_task(node.task) };
+                    // Mark finished before unlinking from `self.nodes`: the
+                    // future's own poll may have already woken (and thus
+                    // relinked) this same node, and that relink must be
+                    // recognised as stale rather than re-polled.
+                    node.finished.store(true, Ordering::Release);
+                    self.remove(node_ptr);
+                    // `ready` is already the unprocessed remainder of this
+                    // drained batch; splice it back rather than abandoning
+                    // it, or those nodes could never be queued again.
+                    self.queue.requeue_chain(ready);
+                    return Poll::Ready(Some(outcome));
+                }
+            }
+        }
+    }
+}
+
+impl<F: Future> Default for Unordered<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Future> Drop for Unordered<F> {
+    fn drop(&mut self) {
+        for node in self.nodes.drain(..) {
+            let node_ptr = node.as_ptr();
+            unsafe {
+                let task = node.as_ref().task;
+                task.as_ref().cancel();
+                release_task(task);
+                // If this node is still linked into the ready queue, release
+                // the reference `ReadyQueue::push` took out for that link
+                // too — nothing will ever drain this queue again to do it
+                // for us, so it would otherwise leak.
+                if (*node_ptr).queued.swap(false, Ordering::AcqRel) {
+                    drop_raw::<F>(node_ptr as *const ());
+                }
+                drop_raw::<F>(node_ptr as *const ());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Task;
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+        fn wake_by_ref(self: &Arc<Self>) {}
+    }
+
+    struct Immediate(Option<u32>);
+
+    impl Future for Immediate {
+        type Output = u32;
+        fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<u32> {
+            Poll::Ready(self.0.take().expect("polled after completion"))
+        }
+    }
+
+    // Regression test: a single drained wake batch used to abandon every
+    // node after the first one found finished, since `queued` was already
+    // `true` for the rest of the chain and a later self-wake was a no-op.
+    #[test]
+    fn drains_all_ready_tasks_from_one_wake_batch() {
+        let mut set = Unordered::new();
+        set.push(FutureTask::spawn(Task::new(), Immediate(Some(1))));
+        set.push(FutureTask::spawn(Task::new(), Immediate(Some(2))));
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            match set.poll_next(&mut cx) {
+                Poll::Ready(Some(TaskOutcome::Output(v))) => outputs.push(v),
+                other => panic!("expected a ready output, got {:?}", match other {
+                    Poll::Pending => "Pending",
+                    Poll::Ready(None) => "Ready(None)",
+                    Poll::Ready(Some(_)) => unreachable!(),
+                }),
+            }
+        }
+        outputs.sort_unstable();
+        assert_eq!(outputs, vec![1, 2]);
+        assert!(matches!(set.poll_next(&mut cx), Poll::Ready(None)));
+    }
+
+    /// Either parked forever, or wakes itself (via `wake_by_ref`, which the
+    /// `Future` contract explicitly permits from inside `poll`) before
+    /// reporting `Ready` on the very next poll.
+    enum MaybeWakesSelf {
+        NeverReady,
+        WakesSelfThenReady(Option<u32>),
+    }
+
+    impl Future for MaybeWakesSelf {
+        type Output = u32;
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+            match &mut *self {
+                MaybeWakesSelf::NeverReady => Poll::Pending,
+                MaybeWakesSelf::WakesSelfThenReady(output) => {
+                    cx.waker().wake_by_ref();
+                    Poll::Ready(output.take().expect("polled after completion"))
+                }
+            }
+        }
+    }
+
+    // Regression test: a node that wakes itself during the very poll that
+    // returns `Ready` used to get relinked onto the ready queue just before
+    // `poll_next` freed it, leaving a dangling pointer for the next
+    // `poll_next` call's `drain()` to dereference.
+    #[test]
+    fn self_waking_future_does_not_corrupt_the_ready_queue() {
+        let mut set = Unordered::new();
+        set.push(FutureTask::spawn(Task::new(), MaybeWakesSelf::NeverReady));
+        set.push(FutureTask::spawn(
+            Task::new(),
+            MaybeWakesSelf::WakesSelfThenReady(Some(42)),
+        ));
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+
+        match set.poll_next(&mut cx) {
+            Poll::Ready(Some(TaskOutcome::Output(v))) => assert_eq!(v, 42),
+            other => panic!("expected the self-waking task's output, got {:?}", match other {
+                Poll::Pending => "Pending",
+                Poll::Ready(None) => "Ready(None)",
+                Poll::Ready(Some(_)) => unreachable!(),
+            }),
+        }
+        // The second call used to dereference the freed node left dangling
+        // by the self-wake above.
+        assert!(matches!(set.poll_next(&mut cx), Poll::Pending));
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex, OnceLock},
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+struct Inner {
+    fired: bool,
+    waker: Option<Waker>,
+}
+
+/// A future that resolves once, after `duration` has elapsed.
+///
+/// Backed by a single shared background thread rather than a dedicated OS
+/// thread per timer, since `zap` has no event loop of its own to register
+/// timers against and a supervised task can spend its whole life backing
+/// off between restarts.
+pub struct Sleep {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Sleep {
+    pub fn new(duration: Duration) -> Self {
+        let inner = Arc::new(Mutex::new(Inner {
+            fired: false,
+            waker: None,
+        }));
+        scheduler().schedule(Instant::now() + duration, Arc::clone(&inner));
+        Sleep { inner }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.inner.lock().unwrap();
+        if state.fired {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// One outstanding deadline, ordered so a [`BinaryHeap`] (a max-heap) pops
+/// the *earliest* deadline first.
+struct Entry {
+    deadline: Instant,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+/// A single background thread servicing every outstanding [`Sleep`] in the
+/// process, so a crash-looping supervisor backing off many tasks at once
+/// parks one thread total rather than one per in-flight backoff.
+struct Scheduler {
+    queue: Mutex<BinaryHeap<Entry>>,
+    added: Condvar,
+}
+
+impl Scheduler {
+    fn schedule(&self, deadline: Instant, inner: Arc<Mutex<Inner>>) {
+        self.queue.lock().unwrap().push(Entry { deadline, inner });
+        self.added.notify_one();
+    }
+
+    /// Services the queue forever: sleeps until the next deadline (or until
+    /// a newer, earlier one is added), then fires every entry whose time
+    /// has come.
+    fn run(&self) {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            match queue.peek() {
+                None => queue = self.added.wait(queue).unwrap(),
+                Some(next) => {
+                    let deadline = next.deadline;
+                    let now = Instant::now();
+                    if deadline <= now {
+                        let due = queue.pop().unwrap();
+                        let mut state = due.inner.lock().unwrap();
+                        state.fired = true;
+                        if let Some(waker) = state.waker.take() {
+                            waker.wake();
+                        }
+                    } else {
+                        let (guard, _) = self.added.wait_timeout(queue, deadline - now).unwrap();
+                        queue = guard;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn scheduler() -> &'static Scheduler {
+    static SCHEDULER: OnceLock<Arc<Scheduler>> = OnceLock::new();
+    SCHEDULER.get_or_init(|| {
+        let scheduler = Arc::new(Scheduler {
+            queue: Mutex::new(BinaryHeap::new()),
+            added: Condvar::new(),
+        });
+        let worker = Arc::clone(&scheduler);
+        thread::Builder::new()
+            .name("zap-timer".into())
+            .spawn(move || worker.run())
+            .expect("failed to spawn the shared timer thread");
+        scheduler
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+    use std::task::Wake;
+
+    struct FlagWaker(AtomicBool);
+
+    impl Wake for FlagWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, AtomicOrdering::SeqCst);
+        }
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.store(true, AtomicOrdering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn resolves_and_wakes_after_elapsing() {
+        let mut sleep = Box::pin(Sleep::new(Duration::from_millis(1)));
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut saw_pending = false;
+        let mut woken = false;
+        loop {
+            match sleep.as_mut().poll(&mut cx) {
+                Poll::Ready(()) => break,
+                Poll::Pending => {
+                    saw_pending = true;
+                    if flag.0.swap(false, AtomicOrdering::SeqCst) {
+                        woken = true;
+                        continue;
+                    }
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+        }
+        // A pending poll leaves its waker behind, and firing always wakes it.
+        assert!(!saw_pending || woken || flag.0.load(AtomicOrdering::SeqCst));
+    }
+
+    // Regression test for the one-OS-thread-per-`Sleep` design: many
+    // concurrent timers used to mean many parked threads. They should all
+    // still resolve when serviced by the one shared timer thread.
+    #[test]
+    fn many_concurrent_sleeps_all_resolve() {
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = Waker::from(Arc::clone(&flag));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut sleeps: Vec<_> = (0..64)
+            .map(|_| Box::pin(Sleep::new(Duration::from_millis(1))))
+            .collect();
+
+        while !sleeps.is_empty() {
+            sleeps.retain_mut(|sleep| sleep.as_mut().poll(&mut cx) == Poll::Pending);
+            if !sleeps.is_empty() {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+}